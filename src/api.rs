@@ -1,10 +1,9 @@
 use rand::Rng;
 use std::path::PathBuf;
-use crate::screen::GameState;
+use crate::{input::{self, InputDevice, InputEvent}, keymap::{FixAction, Keymap}, liquid::LiquidSurface, screen::GameState, text::{CachedTexture, MeasuredText}};
 use ggez::{
     glam::Vec2,
-    graphics::{Canvas, DrawParam, Image, Rect, Text},
-    winit::event::VirtualKeyCode,
+    graphics::{Canvas, Color, DrawParam, Image, Rect},
     Context, GameResult,
 };
 
@@ -28,50 +27,62 @@ pub trait GameObject {
     fn draw(&self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult;
 
     /// Updates this component every frame.
-    fn update(&mut self, _state: &GameState) -> GameResult {
+    fn update(&mut self, _state: &GameState, _keymap: &Keymap) -> GameResult {
         Ok(())
     }
 
-    /// Called when a key is pressed.
+    /// Called when a key or gamepad button is pressed.
     ///
     /// ## Parameters
     /// ```rust
     /// &mut self
     /// ```
-    /// The component that is listening for a key press
+    /// The component that is listening for the input
     /// ```rust
-    /// code: &VirtualKeyCode
+    /// input: InputEvent
     /// ```
-    /// The key pressed.
+    /// The input that was pressed, normalized across devices
+    /// ```rust
+    /// keymap: &Keymap
+    /// ```
+    /// The keymap to resolve this component's fix action against
     ///
-    fn on_key_pressed(&mut self, _code: &VirtualKeyCode) -> bool {
+    fn on_key_pressed(&mut self, _input: InputEvent, _keymap: &Keymap) -> bool {
         false
     }
 
-    /// Called when a key is released.
+    /// Called when a key or gamepad button is released.
     ///
     /// ## Parameters
     /// ```rust
     /// &mut self
     /// ```
-    /// The component that is listening for a key press
+    /// The component that is listening for the input
+    /// ```rust
+    /// input: InputEvent
+    /// ```
+    /// The input that was released, normalized across devices
     /// ```rust
-    /// code: &VirtualKeyCode
+    /// keymap: &Keymap
     /// ```
-    /// The key released.
+    /// The keymap to resolve this component's fix action against
     ///
-    fn on_key_released(&mut self, _code: &VirtualKeyCode) -> GameResult {
+    fn on_key_released(&mut self, _input: InputEvent, _keymap: &Keymap) -> GameResult {
         Ok(())
     }
 }
 
+/// The velocity injected into a [`LiquidSurface`]'s columns when its object breaks.
+const SPLASH_IMPULSE: f32 = 4.;
+
 pub struct FixableGameObject {
     position: Vec2,
     texture: Image,
     broken_texture: Image,
-    pub fix_key: &'static VirtualKeyCode,
+    pub fix_action: FixAction,
     frames_since_broken: Option<i32>,
     pub key_object: Option<KeyPopup>,
+    liquid: Option<LiquidSurface>,
 }
 
 impl FixableGameObject {
@@ -86,9 +97,9 @@ impl FixableGameObject {
     /// ```
     /// - The coordinates of the upper-left corner of this object
     /// ```rust
-    /// fix_key: &'static VirtualKeyCode
+    /// fix_action: FixAction
     /// ```
-    /// - The key that will fix this object when pressed
+    /// - The logical action that will fix this object when triggered
     /// ```rust
     /// ctx: &Context
     /// ```
@@ -97,17 +108,25 @@ impl FixableGameObject {
     /// ### Returns
     /// The newly created `FixableGameObject`.
     ///
-    pub fn new(texture: &str, position: Vec2, fix_key: &'static VirtualKeyCode, ctx: &Context) -> Self {
+    pub fn new(texture: &str, position: Vec2, fix_action: FixAction, ctx: &Context) -> Self {
         Self {
             position,
-            fix_key,
+            fix_action,
             frames_since_broken: None,
             texture: Image::from_path(&ctx.gfx, PathBuf::from(texture.to_owned() + ".png")).unwrap(),
             broken_texture: Image::from_path(&ctx.gfx, PathBuf::from(texture.to_owned() + "_broken.png")).unwrap(),
             key_object: None,
+            liquid: None,
         }
     }
 
+    /// Gives this object a [`LiquidSurface`] that sloshes when it breaks and settles when it's
+    /// fixed. Used by objects that hold a liquid, like the milk carton.
+    pub fn with_liquid_surface(mut self, surface: LiquidSurface) -> Self {
+        self.liquid = Some(surface);
+        self
+    }
+
     /// Returns whether or not this object is currently "broken" and is awaiting keyboard input
     /// to be fixed.
     ///
@@ -127,7 +146,7 @@ impl FixableGameObject {
     } 
 
     /// "Breaks" this object. The texture is updated to the broken version and the timer will be changed.
-    pub fn mess_up(&mut self, state: &GameState) {
+    pub fn mess_up(&mut self, state: &GameState, keymap: &Keymap) {
         self.frames_since_broken = Some(0);
         let dimensions = Vec2::new(
             self.broken_texture.width() as f32,
@@ -138,9 +157,23 @@ impl FixableGameObject {
 
         self.key_object = Some(KeyPopup::new(
             Vec2::new(center.x, bottom),
-            self.fix_key,
-            state.broken_lifetime 
+            self.fix_action,
+            keymap,
+            state.active_device(),
+            state.broken_lifetime()
         ));
+
+        if let Some(liquid) = &mut self.liquid {
+            let middle = liquid.column_count() / 2;
+            liquid.splash(middle, SPLASH_IMPULSE);
+        }
+    }
+
+    /// Immediately fixes this object, bypassing normal key input. Used by the debug overlay.
+    #[cfg(feature = "debug_overlay")]
+    pub fn force_fix(&mut self) {
+        self.frames_since_broken = None;
+        self.key_object = None;
     }
 }
 
@@ -159,12 +192,18 @@ impl GameObject for FixableGameObject {
             DrawParam::new().dest_rect(Rect::new(self.position.x, self.position.y, 6.4, 6.4)),
         );
 
+        if let Some(liquid) = &self.liquid {
+            let dimensions = Vec2::new(texture.width() as f32, texture.height() as f32) * 6.4;
+            let bottom = Vec2::new(self.position.x, self.position.y + dimensions.y);
+            liquid.draw(canvas, bottom, dimensions.x, Color::new(0.95, 0.95, 0.9, 1.))?;
+        }
+
         // Exit with no errors
         Ok(())
     }
 
-    fn on_key_pressed(&mut self, code: &VirtualKeyCode) -> bool {
-        if code == self.fix_key && self.is_broken() {
+    fn on_key_pressed(&mut self, input: InputEvent, keymap: &Keymap) -> bool {
+        if self.is_broken() && keymap.matches(self.fix_action, input) {
             self.frames_since_broken = None;
             self.key_object = None;
             return true;
@@ -173,22 +212,28 @@ impl GameObject for FixableGameObject {
         false
     }
 
-    fn update(&mut self, state: &GameState) -> GameResult {
+    fn update(&mut self, state: &GameState, keymap: &Keymap) -> GameResult {
         if self.is_broken() {
             self.frames_since_broken = Some(self.frames_since_broken.as_ref().unwrap() + 1);
-            self.key_object.as_mut().unwrap().update(state)?;
+            self.key_object.as_mut().unwrap().update(state, keymap)?;
         } else {
             if rand::thread_rng().gen_range(0. ..1.) < state.chance_of_breaking() {
-                self.mess_up(state);
+                self.mess_up(state, keymap);
             }
         }
+
+        if let Some(liquid) = &mut self.liquid {
+            liquid.update();
+        }
+
         Ok(())
     }
 }
 
 pub struct KeyPopup {
     center: Vec2,
-    text: Text,
+    text: MeasuredText,
+    texture: CachedTexture,
     pub frames_existed: i32,
     pub lifetime: i32,
 }
@@ -201,38 +246,44 @@ impl KeyPopup {
     /// ```
     /// - The position for the upper-left corner of the key icon
     /// ```rust
-    /// key: &'static VirtualKeyCode
+    /// fix_action: FixAction
     /// ```
-    /// - A reference to the key that is being displayed
+    /// - The action that needs to be triggered to fix the parent object
     /// ```rust
-    /// ctx: &Context
+    /// keymap: &Keymap
+    /// ```
+    /// - The keymap to resolve `fix_action` against for the active device
+    /// ```rust
+    /// device: InputDevice
+    /// ```
+    /// - Which device most recently produced input, so the correct glyph is shown
+    /// ```rust
+    /// lifetime: i32
     /// ```
-    /// - The drawing context; Used to fetch the image for the texture.
+    /// - The number of frames over which the popup's pulsing scale animation runs
     ///
     /// ### Returns
     /// The newly created key icon object.
-    pub fn new(center: Vec2, key: &'static VirtualKeyCode, lifetime: i32) -> Self {
+    pub fn new(center: Vec2, fix_action: FixAction, keymap: &Keymap, device: InputDevice, lifetime: i32) -> Self {
         // Create the text object
-        let mut text = Text::new(format!("{:?}", key));
-        text.set_font("PixeloidSans");
-        text.set_scale(55.);
+        let label = match device {
+            InputDevice::Keyboard => format!("{:?}", keymap.key_for(fix_action)),
+            InputDevice::Gamepad => input::button_label(keymap.button_for(fix_action)).to_string(),
+        };
 
         // Return the key game object
         Self {
             center,
-            text,
+            text: MeasuredText::new(label, 55.),
+            texture: CachedTexture::new("/key.png"),
             frames_existed: 0,
             lifetime,
         }
     }
-
-    pub fn texture(ctx: &Context) -> Image {
-        Image::from_path(ctx, PathBuf::from("/key.png")).unwrap()
-    }
 }
 
 impl GameObject for KeyPopup {
-    fn update(&mut self, _state: &GameState) -> GameResult {
+    fn update(&mut self, _state: &GameState, _keymap: &Keymap) -> GameResult {
         self.frames_existed += 1;
 
         return Ok(());
@@ -243,12 +294,13 @@ impl GameObject for KeyPopup {
         // Draw the key
         let percent_of_lifetime_used = self.frames_existed as f32 / self.lifetime as f32;
         let scale = 6.4 + (percent_of_lifetime_used * 10.).sin();
+        let texture = self.texture.get(ctx);
 
         canvas.draw(
-            &KeyPopup::texture(ctx),
+            &texture,
             DrawParam::new().dest_rect(Rect::new(
-                self.center.x - KeyPopup::texture(ctx).width() as f32 * scale / 2. - 20.,
-                self.center.y - KeyPopup::texture(ctx).width() as f32 * scale / 2.,
+                self.center.x - texture.width() as f32 * scale / 2. - 20.,
+                self.center.y - texture.width() as f32 * scale / 2.,
                 scale, scale
             )),
         );
@@ -256,11 +308,11 @@ impl GameObject for KeyPopup {
         // Draw the text
         let text_scale = scale / 6.4;
         let text_dimensions = {
-            let dims = self.text.measure(&ctx.gfx)?;
+            let dims = self.text.dimensions(ctx)?;
             Vec2::new(dims.x * text_scale, dims.y * text_scale)
         };
         canvas.draw(
-            &self.text,
+            self.text.text(),
             DrawParam::new().dest_rect(Rect::new(
                 self.center.x - text_dimensions.x / 2. - 17.,
                 self.center.y - text_dimensions.y / 2.,
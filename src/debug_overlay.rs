@@ -0,0 +1,171 @@
+use std::ops::RangeInclusive;
+
+use ggez::{
+    glam::Vec2,
+    graphics::{Canvas, Color, DrawParam, Quad, Rect, Text},
+    winit::event::VirtualKeyCode,
+    Context, GameResult,
+};
+
+use crate::{scene::SceneStack, screen::GAME_STATE};
+
+const PANEL_RECT: Rect = Rect::new(20., 20., 440., 460.);
+const SLIDER_X: f32 = 180.;
+const SLIDER_WIDTH: f32 = 260.;
+const SLIDER_HEIGHT: f32 = 16.;
+const BROKEN_LIFETIME_Y: f32 = 150.;
+const GRACE_PERIOD_Y: f32 = 190.;
+const BROKEN_LIFETIME_RANGE: RangeInclusive<i32> = 10..=1000;
+const GRACE_PERIOD_RANGE: RangeInclusive<i32> = 0..=600;
+
+/// Live per-component stats shown in the debug overlay's component list.
+pub struct ComponentDebugInfo {
+    pub label: String,
+    pub frames_since_broken: Option<i32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Slider {
+    BrokenLifetime,
+    GracePeriod,
+}
+
+impl Slider {
+    fn rect(self) -> Rect {
+        match self {
+            Slider::BrokenLifetime => Rect::new(SLIDER_X, BROKEN_LIFETIME_Y, SLIDER_WIDTH, SLIDER_HEIGHT),
+            Slider::GracePeriod => Rect::new(SLIDER_X, GRACE_PERIOD_Y, SLIDER_WIDTH, SLIDER_HEIGHT),
+        }
+    }
+
+    fn range(self) -> RangeInclusive<i32> {
+        match self {
+            Slider::BrokenLifetime => BROKEN_LIFETIME_RANGE,
+            Slider::GracePeriod => GRACE_PERIOD_RANGE,
+        }
+    }
+
+    fn value(self) -> i32 {
+        match self {
+            Slider::BrokenLifetime => GAME_STATE.broken_lifetime(),
+            Slider::GracePeriod => GAME_STATE.grace_period_frames(),
+        }
+    }
+
+    fn set_from_x(self, x: f32) {
+        let range = self.range();
+        let t = ((x - SLIDER_X) / SLIDER_WIDTH).clamp(0., 1.);
+        let value = *range.start() + (t * (*range.end() - *range.start()) as f32).round() as i32;
+        match self {
+            Slider::BrokenLifetime => GAME_STATE.set_broken_lifetime(value),
+            Slider::GracePeriod => GAME_STATE.set_grace_period_frames(value),
+        }
+    }
+}
+
+/// An immediate-mode panel, toggled with F3, for tuning difficulty and poking at components
+/// without recompiling. Only compiled in when the `debug_overlay` feature is enabled, so it
+/// never ships in release builds.
+pub struct DebugOverlay {
+    pub visible: bool,
+    dragging: Option<Slider>,
+    selected: usize,
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            dragging: None,
+            selected: 0,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        self.dragging = None;
+    }
+
+    pub fn mouse_button_down(&mut self, x: f32, y: f32) {
+        if !self.visible {
+            return;
+        }
+
+        for slider in [Slider::BrokenLifetime, Slider::GracePeriod] {
+            if slider.rect().contains(Vec2::new(x, y)) {
+                slider.set_from_x(x);
+                self.dragging = Some(slider);
+                return;
+            }
+        }
+    }
+
+    pub fn mouse_motion(&mut self, x: f32) {
+        if let Some(slider) = self.dragging {
+            slider.set_from_x(x);
+        }
+    }
+
+    pub fn mouse_button_up(&mut self) {
+        self.dragging = None;
+    }
+
+    /// Handles overlay-specific keys (component select, force-break, instant-fix) while the
+    /// overlay is visible. Does nothing if there are no components to act on.
+    pub fn key_down(&mut self, keycode: VirtualKeyCode, scenes: &mut SceneStack, component_count: usize) {
+        if component_count == 0 {
+            return;
+        }
+
+        match keycode {
+            VirtualKeyCode::Tab => self.selected = (self.selected + 1) % component_count,
+            VirtualKeyCode::Return => scenes.debug_force_break(self.selected),
+            VirtualKeyCode::Back => scenes.debug_instant_fix(self.selected),
+            _ => {}
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context, canvas: &mut Canvas, frame: usize, components: &[ComponentDebugInfo]) -> GameResult {
+        if !self.visible {
+            return Ok(());
+        }
+
+        canvas.draw(&Quad, DrawParam::default().color(Color::new(0., 0., 0., 0.75)).dest_rect(PANEL_RECT));
+
+        let mut header = Text::new(format!(
+            "DEBUG (F3 to close)\nFrame: {frame}\nBreak chance: {:.5}\nBroken lifetime: {}\nGrace period: {} frames",
+            GAME_STATE.chance_of_breaking(),
+            GAME_STATE.broken_lifetime(),
+            GAME_STATE.grace_period_frames(),
+        ));
+        header.set_font("PixeloidSans");
+        header.set_scale(26.);
+        canvas.draw(&header, DrawParam::new().dest(Vec2::new(PANEL_RECT.x + 20., PANEL_RECT.y + 20.)));
+
+        for slider in [Slider::BrokenLifetime, Slider::GracePeriod] {
+            let rect = slider.rect();
+            let range = slider.range();
+            let fill = (slider.value() - range.start()) as f32 / (*range.end() - *range.start()) as f32;
+
+            canvas.draw(&Quad, DrawParam::default().color(Color::WHITE).dest_rect(rect));
+            canvas.draw(&Quad, DrawParam::default().color(Color::GREEN).dest_rect(Rect::new(rect.x, rect.y, rect.w * fill, rect.h)));
+        }
+
+        let mut list = String::from("[Tab] select  [Enter] force-break  [Backspace] fix\n");
+        for (i, component) in components.iter().enumerate() {
+            let marker = if i == self.selected { ">" } else { " " };
+            let state = match component.frames_since_broken {
+                Some(frames) => format!("broken {frames}f ago"),
+                None => "fixed".to_string(),
+            };
+            list.push_str(&format!("{marker} {}: {}\n", component.label, state));
+        }
+
+        let mut list_text = Text::new(list);
+        list_text.set_font("PixeloidSans");
+        list_text.set_scale(20.);
+        canvas.draw(&list_text, DrawParam::new().dest(Vec2::new(PANEL_RECT.x + 20., BROKEN_LIFETIME_Y + 50.)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use ggez::{
+    event::Button as GamepadButton,
+    winit::event::VirtualKeyCode,
+};
+
+/// A physical input, normalized across keyboard and gamepad so callers consuming a "fix action"
+/// don't need to care which device triggered it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEvent {
+    Key(VirtualKeyCode),
+    GamepadButton(GamepadButton),
+}
+
+/// Which device most recently produced input, so `KeyPopup` can show the matching glyph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputDevice {
+    Keyboard,
+    Gamepad,
+}
+
+/// A human-readable label for a gamepad button, used in place of the raw `{:?}` debug string.
+pub fn button_label(button: GamepadButton) -> &'static str {
+    match button {
+        GamepadButton::South => "A",
+        GamepadButton::East => "B",
+        GamepadButton::West => "X",
+        GamepadButton::North => "Y",
+        GamepadButton::LeftTrigger => "LB",
+        GamepadButton::LeftTrigger2 => "LT",
+        GamepadButton::RightTrigger => "RB",
+        GamepadButton::RightTrigger2 => "RT",
+        GamepadButton::Select => "Select",
+        GamepadButton::Start => "Start",
+        _ => "?",
+    }
+}
+
+/// A human-readable label for whichever input produced `event`.
+pub fn describe(event: InputEvent) -> String {
+    match event {
+        InputEvent::Key(key) => format!("{key:?}"),
+        InputEvent::GamepadButton(button) => button_label(button).to_string(),
+    }
+}
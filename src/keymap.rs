@@ -0,0 +1,127 @@
+use std::{collections::HashMap, io::Read};
+
+use ggez::{
+    event::{Axis as GamepadAxis, Button as GamepadButton},
+    winit::event::VirtualKeyCode,
+    Context,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::input::InputEvent;
+
+/// A logical action that can be bound to a physical key or gamepad input, independent of which
+/// one actually triggers it. `FixWindow`/`FixMilk`/etc. are the pool of "fix" slots used by the
+/// breakable components created in `create_objects`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FixAction {
+    Quit,
+    FixWindow,
+    FixMilk,
+    FixLamp,
+    FixDrawer,
+    FixRug,
+}
+
+/// A configurable mapping from logical [`FixAction`]s to physical inputs. Keyboard bindings are
+/// loaded from `keymap.json` so players on non-QWERTY layouts can remap the game; gamepad
+/// bindings are fixed defaults, since a controller's layout is already standardized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keymap {
+    bindings: HashMap<FixAction, VirtualKeyCode>,
+    #[serde(skip, default = "Keymap::default_buttons")]
+    buttons: HashMap<FixAction, GamepadButton>,
+    #[serde(skip, default = "Keymap::default_axes")]
+    axes: HashMap<FixAction, GamepadAxis>,
+}
+
+impl Keymap {
+    /// Loads the keymap from `/keymap.json` in the resource directory, falling back to
+    /// [`Keymap::default`] when the file is absent or fails to parse.
+    pub fn load(ctx: &Context) -> Keymap {
+        let Ok(mut file) = ctx.fs.open("/keymap.json") else {
+            return Keymap::default();
+        };
+
+        let mut contents = String::new();
+        if file.read_to_string(&mut contents).is_err() {
+            return Keymap::default();
+        }
+
+        serde_json::from_str(&contents).unwrap_or_else(|_| Keymap::default())
+    }
+
+    /// Returns the key currently bound to `action`, falling back to the default binding if the
+    /// action is missing from the map (e.g. it was added after the player's config was saved).
+    pub fn key_for(&self, action: FixAction) -> VirtualKeyCode {
+        match self.bindings.get(&action) {
+            Some(key) => *key,
+            None => *Keymap::default().bindings.get(&action).expect("every action has a default binding"),
+        }
+    }
+
+    /// Returns the gamepad button bound to `action`.
+    pub fn button_for(&self, action: FixAction) -> GamepadButton {
+        *self.buttons.get(&action).expect("every action has a default gamepad binding")
+    }
+
+    /// Returns the `FixAction` bound to `axis`, if any. Used to normalize analog trigger axes
+    /// into the same fix-action presses/releases as digital buttons.
+    pub fn action_for_axis(&self, axis: GamepadAxis) -> Option<FixAction> {
+        self.axes.iter().find(|(_, bound_axis)| **bound_axis == axis).map(|(action, _)| *action)
+    }
+
+    /// Returns the `FixAction` bound to `button`, if any.
+    pub fn action_for_button(&self, button: GamepadButton) -> Option<FixAction> {
+        self.buttons.iter().find(|(_, bound_button)| **bound_button == button).map(|(action, _)| *action)
+    }
+
+    /// Returns whether `action` is also driven by an analog axis (e.g. an analog trigger that
+    /// reports both a digital button press and an axis movement for the same pull). Gamepad
+    /// button events for these actions are ignored in favor of the axis event, so a single
+    /// physical trigger pull doesn't resolve twice.
+    pub fn is_axis_backed(&self, action: FixAction) -> bool {
+        self.axes.contains_key(&action)
+    }
+
+    /// Returns whether `input` currently triggers `action`, across either device.
+    pub fn matches(&self, action: FixAction, input: InputEvent) -> bool {
+        match input {
+            InputEvent::Key(key) => self.key_for(action) == key,
+            InputEvent::GamepadButton(button) => self.button_for(action) == button,
+        }
+    }
+
+    fn default_buttons() -> HashMap<FixAction, GamepadButton> {
+        let mut buttons = HashMap::new();
+        buttons.insert(FixAction::Quit, GamepadButton::Start);
+        buttons.insert(FixAction::FixWindow, GamepadButton::North);
+        buttons.insert(FixAction::FixMilk, GamepadButton::West);
+        buttons.insert(FixAction::FixLamp, GamepadButton::East);
+        buttons.insert(FixAction::FixDrawer, GamepadButton::South);
+        buttons.insert(FixAction::FixRug, GamepadButton::LeftTrigger2);
+        buttons
+    }
+
+    fn default_axes() -> HashMap<FixAction, GamepadAxis> {
+        let mut axes = HashMap::new();
+        axes.insert(FixAction::FixRug, GamepadAxis::LeftZ);
+        axes
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        let mut bindings = HashMap::new();
+        bindings.insert(FixAction::Quit, VirtualKeyCode::Escape);
+        bindings.insert(FixAction::FixWindow, VirtualKeyCode::W);
+        bindings.insert(FixAction::FixMilk, VirtualKeyCode::M);
+        bindings.insert(FixAction::FixLamp, VirtualKeyCode::L);
+        bindings.insert(FixAction::FixDrawer, VirtualKeyCode::D);
+        bindings.insert(FixAction::FixRug, VirtualKeyCode::R);
+        Keymap {
+            bindings,
+            buttons: Keymap::default_buttons(),
+            axes: Keymap::default_axes(),
+        }
+    }
+}
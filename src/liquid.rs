@@ -0,0 +1,97 @@
+use ggez::{
+    glam::Vec2,
+    graphics::{Canvas, Color, DrawParam, Quad, Rect},
+    GameResult,
+};
+
+/// How strongly a column is pulled back toward its rest height each frame.
+const TENSION: f32 = 0.025;
+
+/// How strongly a column's velocity is bled off each frame, so the surface settles instead of
+/// oscillating forever.
+const DAMPENING: f32 = 0.025;
+
+/// How strongly a disturbance spreads from a column into its neighbors each pass.
+const SPREAD: f32 = 0.25;
+
+/// A reusable 1-D spring-mass water simulation: a row of columns that each bob toward a rest
+/// height, spreading disturbances to their neighbors so a poke on one side ripples across the
+/// whole surface. Any [`crate::api::FixableGameObject`] can carry one to get a sloshing liquid
+/// effect, not just milk.
+pub struct LiquidSurface {
+    heights: Vec<f32>,
+    targets: Vec<f32>,
+    velocities: Vec<f32>,
+}
+
+impl LiquidSurface {
+    /// Creates a new, resting liquid surface split into `columns` columns, all starting at
+    /// `rest_height`.
+    pub fn new(columns: usize, rest_height: f32) -> Self {
+        Self {
+            heights: vec![rest_height; columns],
+            targets: vec![rest_height; columns],
+            velocities: vec![0.; columns],
+        }
+    }
+
+    /// Advances the simulation by one frame: a spring step pulling every column toward its
+    /// target, followed by two neighbor-spreading passes so disturbances ripple outward.
+    pub fn update(&mut self) {
+        for i in 0..self.heights.len() {
+            let accel = TENSION * (self.targets[i] - self.heights[i]) - DAMPENING * self.velocities[i];
+            self.velocities[i] += accel;
+            self.heights[i] += self.velocities[i];
+        }
+
+        for _ in 0..2 {
+            let mut deltas = vec![0.; self.heights.len()];
+            for i in 0..self.heights.len() {
+                if i > 0 {
+                    deltas[i - 1] += SPREAD * (self.heights[i] - self.heights[i - 1]);
+                }
+                if i + 1 < self.heights.len() {
+                    deltas[i + 1] += SPREAD * (self.heights[i] - self.heights[i + 1]);
+                }
+            }
+
+            for (velocity, delta) in self.velocities.iter_mut().zip(deltas) {
+                *velocity += delta;
+            }
+        }
+    }
+
+    /// How many columns this surface is split into.
+    pub fn column_count(&self) -> usize {
+        self.heights.len()
+    }
+
+    /// Injects a velocity impulse into the column at `index` and its immediate neighbors, to
+    /// kick off a splash.
+    pub fn splash(&mut self, index: usize, impulse: f32) {
+        for offset in [-1isize, 0, 1] {
+            let Some(i) = index.checked_add_signed(offset) else {
+                continue;
+            };
+            if let Some(velocity) = self.velocities.get_mut(i) {
+                *velocity += impulse;
+            }
+        }
+    }
+
+    /// Draws the surface as a row of thin quads rising from `bottom` up to each column's
+    /// current height, spanning `width` pixels wide starting at `bottom.x`.
+    pub fn draw(&self, canvas: &mut Canvas, bottom: Vec2, width: f32, color: Color) -> GameResult {
+        let column_width = width / self.heights.len() as f32;
+
+        for (i, &height) in self.heights.iter().enumerate() {
+            let x = bottom.x + i as f32 * column_width;
+            canvas.draw(
+                &Quad,
+                DrawParam::default().color(color).dest_rect(Rect::new(x, bottom.y - height, column_width, height)),
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -3,13 +3,24 @@ use screen::Window;
 use util::get_resource_dir;
 
 mod api;
+#[cfg(feature = "debug_overlay")]
+mod debug_overlay;
+mod input;
+mod keymap;
+mod liquid;
 mod pause;
+mod scene;
 mod screen;
+mod text;
+mod title;
 mod util;
 
 /// The main function; Creates the game loop.
 pub fn main() -> GameResult {
-    
+    if std::env::args().any(|arg| arg == "--more-rust") {
+        text::set_more_rust(true);
+    }
+
     // Create the context and event loop
     let (mut ctx, event_loop) = ContextBuilder::new("fixit", "Neph Iapalucci").add_resource_path(get_resource_dir()).build()?;
     ctx.gfx.set_window_title("Fixit");
@@ -17,7 +28,7 @@ pub fn main() -> GameResult {
     ctx.gfx.add_font("PixeloidSans", FontData::from_path(&ctx, "/fonts/PixeloidSans.ttf")?);
 
     // Create the main window
-    let window = Window::new(&ctx);
+    let window = Window::new(&mut ctx);
 
     // Run the event loop
     event::run(ctx, event_loop, window);
@@ -1,57 +1,101 @@
-use ggez::{Context, graphics::{Canvas, DrawParam, Text, Rect, Quad, Color}, GameResult, glam::Vec2, winit::event::VirtualKeyCode};
+use ggez::{Context, graphics::{Canvas, DrawParam, Rect, Quad, Color}, input::keyboard::KeyInput, GameResult, glam::Vec2};
 
-use crate::api::GameObject;
+use crate::{
+    input::{self, InputEvent},
+    keymap::Keymap,
+    scene::{Scene, SceneTransition},
+    text::MeasuredText,
+    title::TitleScene,
+    util::{FileSaveStore, SaveData, SaveStore},
+};
 
 pub struct GameOverScreen {
-    title: Text,
-    description: Text
+    title: MeasuredText,
+    description: MeasuredText,
+    score: MeasuredText,
+    keymap: Keymap,
+    save_data: SaveData,
 }
 
-pub enum GameOverCause<'a> {
-    WrongKey(&'a VirtualKeyCode),
-    NotInTime(&'a VirtualKeyCode)
+#[derive(Clone, Copy)]
+pub enum GameOverCause {
+    WrongInput(InputEvent),
+    NotInTime(InputEvent)
+}
+
+/// The outcome of a single run, used to update the persisted [`SaveData`] and to show the
+/// player how they did.
+pub struct RunStats {
+    pub frames_survived: usize,
+    pub fixes: u32,
+    pub chance_of_breaking: f32,
 }
 
 impl GameOverScreen {
-    pub fn new(cause: GameOverCause) -> Self {
-        let mut title = Text::new("Game Over");
-        title.set_font("PixeloidSans");
-        title.set_scale(100.);
+    pub fn new(cause: GameOverCause, stats: RunStats, previous_best: SaveData, keymap: Keymap) -> Self {
+        let title = MeasuredText::new("Game Over", 100.);
 
-        let mut description = Text::new(
+        let description = MeasuredText::new(
             match cause {
-                GameOverCause::WrongKey(key) => format!("You pressed {:?} when nothing needed it.", key),
-                GameOverCause::NotInTime(key) => format!("You waited too long to press {:?}.", key)
-            }
+                GameOverCause::WrongInput(input) => format!("You pressed {} when nothing needed it.", input::describe(input)),
+                GameOverCause::NotInTime(input) => format!("You waited too long to press {}.", input::describe(input))
+            },
+            100.
         );
-        description.set_font("PixeloidSans");
-        description.set_scale(100.);
+
+        let beat_record = stats.frames_survived > previous_best.longest_survival_frames;
+        let score = MeasuredText::new(format!(
+            "Survived {} frames and fixed {} things.{}",
+            stats.frames_survived,
+            stats.fixes,
+            if beat_record { " New record!" } else { "" }
+        ), 60.);
+
+        let updated_save = SaveData {
+            longest_survival_frames: previous_best.longest_survival_frames.max(stats.frames_survived),
+            total_fixes: previous_best.total_fixes + stats.fixes,
+            highest_chance_of_breaking: previous_best.highest_chance_of_breaking.max(stats.chance_of_breaking),
+        };
+        FileSaveStore::new().write(&updated_save);
 
         Self {
             title,
-            description
+            description,
+            score,
+            keymap,
+            save_data: updated_save,
         }
     }
 }
- 
-impl GameObject for GameOverScreen{
-    
+
+impl Scene for GameOverScreen {
+
+    fn key_down(&mut self, _ctx: &mut Context, input: KeyInput, _repeat: bool) -> GameResult<SceneTransition> {
+        if input.keycode.is_some() {
+            return Ok(SceneTransition::Replace(Box::new(TitleScene::new(self.keymap.clone(), self.save_data))));
+        }
+        Ok(SceneTransition::None)
+    }
+
     fn draw(&self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
         static TEXT_SCALE: f32 = 2.;
         let text_dimensions = {
-            let dims = self.title.measure(&ctx.gfx)?;
+            let dims = self.title.dimensions(ctx)?;
             Vec2::new(dims.x * TEXT_SCALE, dims.y * TEXT_SCALE)
         };
-        
+
         canvas.draw(&Quad, DrawParam::default().color(Color::BLACK).dest_rect(Rect::new(0., 0., 1920., 1080.)));
-        canvas.draw(&self.title, DrawParam::new().scale(Vec2::new(TEXT_SCALE, TEXT_SCALE)).dest(Vec2::new(1920. / 2. - text_dimensions.x / 2., 20.)));
-        
+        canvas.draw(self.title.text(), DrawParam::new().scale(Vec2::new(TEXT_SCALE, TEXT_SCALE)).dest(Vec2::new(1920. / 2. - text_dimensions.x / 2., 20.)));
+
         let desc_scale = 1.;
         let desc_dimensions = {
-            let dims = self.description.measure(&ctx.gfx)?;
+            let dims = self.description.dimensions(ctx)?;
             Vec2::new(dims.x * desc_scale, dims.y * desc_scale)
         };
-        canvas.draw(&self.description, DrawParam::new().dest(Vec2::new(1920. / 2. - desc_dimensions.x / 2., 300.)));
+        canvas.draw(self.description.text(), DrawParam::new().dest(Vec2::new(1920. / 2. - desc_dimensions.x / 2., 300.)));
+
+        let score_dimensions = self.score.dimensions(ctx)?;
+        canvas.draw(self.score.text(), DrawParam::new().dest(Vec2::new(1920. / 2. - score_dimensions.x / 2., 450.)));
 
         Ok(())
     }
@@ -0,0 +1,171 @@
+use ggez::{graphics::Canvas, input::keyboard::KeyInput, Context, GameResult};
+
+use crate::input::InputEvent;
+
+/// A transition the active [`Scene`] can request after handling an event.
+///
+/// Returned from every [`Scene`] callback instead of mutating engine state directly, so the
+/// [`SceneStack`] stays the single place that knows how to push, pop, or replace scenes.
+pub enum SceneTransition {
+    /// Push a new scene on top of the stack, leaving the current one underneath.
+    Push(Box<dyn Scene>),
+    /// Pop the active scene off the stack, resuming whatever is beneath it.
+    Pop,
+    /// Replace the active scene with a new one, without affecting the rest of the stack.
+    Replace(Box<dyn Scene>),
+    /// Stay on the active scene.
+    None,
+}
+
+/// A single screen of the game (title, gameplay, game over, ...) that owns its own input
+/// handling. The [`SceneStack`] forwards `EventHandler` callbacks to whichever scene is on top.
+pub trait Scene {
+    /// Called once when this scene becomes the active scene.
+    fn init(&mut self, _ctx: &mut Context) -> GameResult {
+        Ok(())
+    }
+
+    /// Called every frame while this scene is active.
+    fn tick(&mut self, _ctx: &mut Context) -> GameResult<SceneTransition> {
+        Ok(SceneTransition::None)
+    }
+
+    /// Draws this scene to the canvas. Only the topmost scene on the stack is drawn.
+    fn draw(&self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult;
+
+    /// Called when a key is pressed while this scene is active.
+    fn key_down(&mut self, _ctx: &mut Context, _input: KeyInput, _repeat: bool) -> GameResult<SceneTransition> {
+        Ok(SceneTransition::None)
+    }
+
+    /// Called when a key is released while this scene is active.
+    fn key_up(&mut self, _ctx: &mut Context, _input: KeyInput) -> GameResult<SceneTransition> {
+        Ok(SceneTransition::None)
+    }
+
+    /// Called when a gamepad button (or an axis standing in for one) is pressed while this
+    /// scene is active. Keyboard input keeps flowing through [`Scene::key_down`]; this exists
+    /// so scenes that care about the normalized [`InputEvent`] don't need a `KeyInput` to react.
+    fn input_down(&mut self, _ctx: &mut Context, _input: InputEvent) -> GameResult<SceneTransition> {
+        Ok(SceneTransition::None)
+    }
+
+    /// Called when a gamepad button (or an axis standing in for one) is released while this
+    /// scene is active.
+    fn input_up(&mut self, _ctx: &mut Context, _input: InputEvent) -> GameResult<SceneTransition> {
+        Ok(SceneTransition::None)
+    }
+
+    /// Returns live debug info for whatever components this scene owns, for the debug overlay.
+    #[cfg(feature = "debug_overlay")]
+    fn debug_components(&self) -> Vec<crate::debug_overlay::ComponentDebugInfo> {
+        Vec::new()
+    }
+
+    /// Forces the component at `index` (as returned by [`Scene::debug_components`]) to break.
+    #[cfg(feature = "debug_overlay")]
+    fn debug_force_break(&mut self, _index: usize) {}
+
+    /// Instantly fixes the component at `index` (as returned by [`Scene::debug_components`]).
+    #[cfg(feature = "debug_overlay")]
+    fn debug_instant_fix(&mut self, _index: usize) {}
+
+    /// Returns this scene's current frame counter, for the debug overlay.
+    #[cfg(feature = "debug_overlay")]
+    fn debug_frame(&self) -> usize {
+        0
+    }
+}
+
+/// A stack of [`Scene`]s, dispatching engine callbacks to the topmost scene only.
+///
+/// Adding a new screen (a settings menu, a real pause overlay, ...) is a matter of pushing
+/// another `Scene` onto the stack instead of threading another `Option` through `Window`.
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    /// Creates a new scene stack with `initial` as the only (and active) scene.
+    pub fn new(ctx: &mut Context, initial: Box<dyn Scene>) -> GameResult<Self> {
+        let mut stack = Self { scenes: vec![initial] };
+        stack.top_mut().init(ctx)?;
+        Ok(stack)
+    }
+
+    fn top_mut(&mut self) -> &mut Box<dyn Scene> {
+        self.scenes.last_mut().expect("scene stack should never be empty")
+    }
+
+    fn apply(&mut self, ctx: &mut Context, transition: SceneTransition) -> GameResult {
+        match transition {
+            SceneTransition::Push(mut scene) => {
+                scene.init(ctx)?;
+                self.scenes.push(scene);
+            }
+            SceneTransition::Pop => {
+                self.scenes.pop();
+            }
+            SceneTransition::Replace(mut scene) => {
+                scene.init(ctx)?;
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+            SceneTransition::None => {}
+        }
+        Ok(())
+    }
+
+    pub fn tick(&mut self, ctx: &mut Context) -> GameResult {
+        let transition = self.top_mut().tick(ctx)?;
+        self.apply(ctx, transition)
+    }
+
+    pub fn draw(&self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        self.scenes.last().expect("scene stack should never be empty").draw(ctx, canvas)
+    }
+
+    pub fn key_down(&mut self, ctx: &mut Context, input: KeyInput, repeat: bool) -> GameResult {
+        let transition = self.top_mut().key_down(ctx, input, repeat)?;
+        self.apply(ctx, transition)
+    }
+
+    pub fn key_up(&mut self, ctx: &mut Context, input: KeyInput) -> GameResult {
+        let transition = self.top_mut().key_up(ctx, input)?;
+        self.apply(ctx, transition)
+    }
+
+    pub fn input_down(&mut self, ctx: &mut Context, input: InputEvent) -> GameResult {
+        let transition = self.top_mut().input_down(ctx, input)?;
+        self.apply(ctx, transition)
+    }
+
+    pub fn input_up(&mut self, ctx: &mut Context, input: InputEvent) -> GameResult {
+        let transition = self.top_mut().input_up(ctx, input)?;
+        self.apply(ctx, transition)
+    }
+
+    #[cfg(feature = "debug_overlay")]
+    pub fn debug_components(&self) -> Vec<crate::debug_overlay::ComponentDebugInfo> {
+        self.scenes.last().map(|scene| scene.debug_components()).unwrap_or_default()
+    }
+
+    #[cfg(feature = "debug_overlay")]
+    pub fn debug_force_break(&mut self, index: usize) {
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.debug_force_break(index);
+        }
+    }
+
+    #[cfg(feature = "debug_overlay")]
+    pub fn debug_instant_fix(&mut self, index: usize) {
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.debug_instant_fix(index);
+        }
+    }
+
+    #[cfg(feature = "debug_overlay")]
+    pub fn debug_frame(&self) -> usize {
+        self.scenes.last().map(|scene| scene.debug_frame()).unwrap_or(0)
+    }
+}
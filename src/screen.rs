@@ -1,181 +1,437 @@
+use std::collections::HashSet;
 use std::process;
+use std::sync::atomic::{AtomicI32, AtomicU8, Ordering};
 
 use ggez::{
-    event::EventHandler,
+    event::{Axis as GamepadAxis, Button as GamepadButton, EventHandler},
     glam::Vec2,
-    graphics::{Canvas, DrawParam, Image, Rect, Sampler, Quad, Color},
+    graphics::{Canvas, Color, DrawParam, Image, Quad, Rect, Sampler},
     input::keyboard::KeyInput,
-    winit::event::VirtualKeyCode,
     Context, GameResult,
 };
 
-use crate::{api::{FixableGameObject, GameObject}, pause::{GameOverScreen, GameOverCause}};
+#[cfg(feature = "debug_overlay")]
+use crate::debug_overlay::DebugOverlay;
+#[cfg(feature = "debug_overlay")]
+use ggez::winit::event::VirtualKeyCode;
+
+use crate::{
+    api::{FixableGameObject, GameObject},
+    input::{InputDevice, InputEvent},
+    keymap::{FixAction, Keymap},
+    liquid::LiquidSurface,
+    pause::{GameOverCause, GameOverScreen, RunStats},
+    scene::{Scene, SceneStack, SceneTransition},
+    title::TitleScene,
+    util::{FileSaveStore, SaveData, SaveStore},
+};
+
+/// How far an analog axis has to move off of center before it counts as "held", so a resting
+/// stick doesn't register as a constant input.
+const AXIS_PRESS_THRESHOLD: f32 = 0.5;
 
+/// The game's difficulty knobs. Stored as atomics (rather than plain fields) so the debug
+/// overlay can tune them at runtime without needing `&mut` access from across the scene stack.
 pub struct GameState {
-    pub broken_lifetime: i32
+    broken_lifetime: AtomicI32,
+    grace_period_frames: AtomicI32,
+    active_device: AtomicU8,
 }
 
 impl GameState {
     pub fn chance_of_breaking(&self) -> f32 {
-        self.broken_lifetime as f32 * 0.00001
+        self.broken_lifetime() as f32 * 0.00001
+    }
+
+    pub fn broken_lifetime(&self) -> i32 {
+        self.broken_lifetime.load(Ordering::Relaxed)
+    }
+
+    pub fn grace_period_frames(&self) -> i32 {
+        self.grace_period_frames.load(Ordering::Relaxed)
+    }
+
+    /// Which device most recently produced input, so `KeyPopup` can show the matching glyph.
+    pub fn active_device(&self) -> InputDevice {
+        match self.active_device.load(Ordering::Relaxed) {
+            1 => InputDevice::Gamepad,
+            _ => InputDevice::Keyboard,
+        }
+    }
+
+    pub fn set_active_device(&self, device: InputDevice) {
+        let value = match device {
+            InputDevice::Keyboard => 0,
+            InputDevice::Gamepad => 1,
+        };
+        self.active_device.store(value, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "debug_overlay")]
+    pub fn set_broken_lifetime(&self, value: i32) {
+        self.broken_lifetime.store(value, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "debug_overlay")]
+    pub fn set_grace_period_frames(&self, value: i32) {
+        self.grace_period_frames.store(value, Ordering::Relaxed);
     }
 }
 
-static GAME_STATE: GameState = GameState {
-    broken_lifetime: 120
+pub(crate) static GAME_STATE: GameState = GameState {
+    broken_lifetime: AtomicI32::new(120),
+    grace_period_frames: AtomicI32::new(120),
+    active_device: AtomicU8::new(0),
 };
 
+/// The engine shell; owns the `SceneStack` and forwards every `EventHandler` callback to
+/// whichever scene is currently on top of it.
 pub struct Window {
-    frame: usize,
-    components: Vec<FixableGameObject>,
-    background: Image,
-    menu: Option<Box<dyn GameObject>>
+    scenes: SceneStack,
+    keymap: Keymap,
+    /// Fix actions currently "held" by an analog axis, so a return to center can be turned into
+    /// a release instead of just silently going quiet.
+    held_axis_actions: HashSet<FixAction>,
+    #[cfg(feature = "debug_overlay")]
+    debug_overlay: DebugOverlay,
 }
 
 impl Window {
+    /// Creates a new window, starting on the title scene.
+    pub fn new(ctx: &mut Context) -> Window {
+        let keymap = Keymap::load(ctx);
+        let save_data = FileSaveStore::new().read().unwrap_or_default();
+        Window {
+            scenes: SceneStack::new(ctx, Box::new(TitleScene::new(keymap.clone(), save_data))).unwrap(),
+            keymap,
+            held_axis_actions: HashSet::new(),
+            #[cfg(feature = "debug_overlay")]
+            debug_overlay: DebugOverlay::new(),
+        }
+    }
+}
+
+impl EventHandler for Window {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        self.scenes.tick(ctx)?;
+        std::thread::yield_now();
+        Ok(())
+    }
+
+    fn key_down_event(&mut self, ctx: &mut Context, input: KeyInput, repeat: bool) -> GameResult {
+        GAME_STATE.set_active_device(InputDevice::Keyboard);
+
+        if input.keycode == Some(self.keymap.key_for(FixAction::Quit)) {
+            process::exit(0);
+        }
+
+        #[cfg(feature = "debug_overlay")]
+        if let Some(keycode) = input.keycode {
+            if keycode == VirtualKeyCode::F3 {
+                self.debug_overlay.toggle();
+                return Ok(());
+            }
+
+            if self.debug_overlay.visible {
+                let component_count = self.scenes.debug_components().len();
+                self.debug_overlay.key_down(keycode, &mut self.scenes, component_count);
+                return Ok(());
+            }
+        }
+
+        self.scenes.key_down(ctx, input, repeat)
+    }
+
+    fn key_up_event(&mut self, ctx: &mut Context, input: KeyInput) -> GameResult {
+        self.scenes.key_up(ctx, input)
+    }
 
-    pub fn pause(&mut self, menu: Box<dyn GameObject>) {
-        self.menu = Some(menu);
+    /// A physical analog trigger reports both a button press and an axis movement for the same
+    /// pull. Those actions are resolved exclusively through `gamepad_axis_event`, so this skips
+    /// them here rather than resolving the same press twice.
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, button: GamepadButton, _id: ggez::event::GamepadId) -> GameResult {
+        if self.keymap.action_for_button(button).is_some_and(|action| self.keymap.is_axis_backed(action)) {
+            return Ok(());
+        }
+
+        GAME_STATE.set_active_device(InputDevice::Gamepad);
+        self.scenes.input_down(ctx, InputEvent::GamepadButton(button))
+    }
+
+    fn gamepad_button_up_event(&mut self, ctx: &mut Context, button: GamepadButton, _id: ggez::event::GamepadId) -> GameResult {
+        if self.keymap.action_for_button(button).is_some_and(|action| self.keymap.is_axis_backed(action)) {
+            return Ok(());
+        }
+
+        self.scenes.input_up(ctx, InputEvent::GamepadButton(button))
     }
 
-    /// Creates a new window.
-    pub fn new(ctx: &Context) -> Window {
-        let mut window = Window {
+    /// Normalizes a continuous analog axis into the same press/release events as a digital
+    /// button: crossing `AXIS_PRESS_THRESHOLD` is a press, and dropping back below it (such as
+    /// returning to center) is a release.
+    fn gamepad_axis_event(&mut self, ctx: &mut Context, axis: GamepadAxis, value: f32, _id: ggez::event::GamepadId) -> GameResult {
+        let Some(action) = self.keymap.action_for_axis(axis) else {
+            return Ok(());
+        };
+        let button = InputEvent::GamepadButton(self.keymap.button_for(action));
+
+        if value.abs() >= AXIS_PRESS_THRESHOLD {
+            if self.held_axis_actions.insert(action) {
+                GAME_STATE.set_active_device(InputDevice::Gamepad);
+                self.scenes.input_down(ctx, button)?;
+            }
+        } else if self.held_axis_actions.remove(&action) {
+            self.scenes.input_up(ctx, button)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "debug_overlay")]
+    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: ggez::input::mouse::MouseButton, x: f32, y: f32) -> GameResult {
+        if button == ggez::input::mouse::MouseButton::Left {
+            self.debug_overlay.mouse_button_down(x, y);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "debug_overlay")]
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, _button: ggez::input::mouse::MouseButton, _x: f32, _y: f32) -> GameResult {
+        self.debug_overlay.mouse_button_up();
+        Ok(())
+    }
+
+    #[cfg(feature = "debug_overlay")]
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, _y: f32, _dx: f32, _dy: f32) -> GameResult {
+        self.debug_overlay.mouse_motion(x);
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        let mut canvas = Canvas::from_frame(ctx, None);
+        canvas.set_sampler(Sampler::nearest_clamp());
+
+        self.scenes.draw(ctx, &mut canvas)?;
+
+        #[cfg(feature = "debug_overlay")]
+        {
+            let components = self.scenes.debug_components();
+            let frame = self.scenes.debug_frame();
+            self.debug_overlay.draw(ctx, &mut canvas, frame, &components)?;
+        }
+
+        canvas.finish(ctx)?;
+
+        Ok(())
+    }
+}
+
+/// The gameplay scene; owns the breakable components and drives the core "keep the house from
+/// falling apart" loop.
+pub struct PlayScene {
+    frame: usize,
+    fixes: u32,
+    components: Vec<FixableGameObject>,
+    background: Image,
+    save_data: SaveData,
+    keymap: Keymap,
+}
+
+impl PlayScene {
+    /// Creates a new gameplay scene, populated with the default set of breakable components.
+    /// Each component stores its `FixAction` identity rather than a resolved key or button, so
+    /// re-binding the keymap mid-run (or switching devices) doesn't require rebuilding them.
+    pub fn new(ctx: &Context, keymap: &Keymap, save_data: SaveData) -> PlayScene {
+        let mut scene = PlayScene {
             frame: 0,
+            fixes: 0,
             components: Vec::new(),
             background: Image::from_path(ctx, "/background.png").unwrap(),
-            menu: None
+            save_data,
+            keymap: keymap.clone(),
         };
 
         for component in create_objects(ctx) {
-            window.add_component(component);
+            scene.add_component(component);
         }
 
-        window
+        scene
     }
 
-    /// Adds a component to be drawn on the screen.
+    /// Adds a component to be drawn and updated by this scene.
     ///
     /// **Parameters**
     /// ```rust
     /// &mut self
     /// ```
-    /// - The window to add the component to
+    /// - The scene to add the component to
     /// ```rust
-    /// child: impl GameObject + 'static
+    /// child: FixableGameObject
     /// ```
-    /// - The `GameObject` component to add
+    /// - The `FixableGameObject` component to add
     pub fn add_component(&mut self, child: FixableGameObject) {
         self.components.push(child);
     }
 
-    pub fn is_paused(&self) -> bool {
-        self.menu.is_some()
+    fn run_stats(&self) -> RunStats {
+        RunStats {
+            frames_survived: self.frame,
+            fixes: self.fixes,
+            chance_of_breaking: GAME_STATE.chance_of_breaking(),
+        }
     }
-}
 
-impl EventHandler for Window {
-    fn update(&mut self, _ctx: &mut Context) -> GameResult {
-        self.frame += 1;
+    /// Resolves `action` to whichever input currently triggers it on the active device, for
+    /// describing a game-over cause.
+    fn input_for_action(&self, action: FixAction) -> InputEvent {
+        match GAME_STATE.active_device() {
+            InputDevice::Keyboard => InputEvent::Key(self.keymap.key_for(action)),
+            InputDevice::Gamepad => InputEvent::GamepadButton(self.keymap.button_for(action)),
+        }
+    }
 
-        if self.is_paused() {
-            return Ok(())
+    fn try_fix(&mut self, input: InputEvent) -> GameResult<SceneTransition> {
+        let mut fixes_this_press = 0;
+        for component in &mut self.components {
+            if component.on_key_pressed(input, &self.keymap) {
+                fixes_this_press += 1;
+            }
         }
 
-        // Update components
-        let mut game_over_key: Option<&VirtualKeyCode> = None;
+        if fixes_this_press == 0 {
+            let stats = self.run_stats();
+            return Ok(SceneTransition::Replace(Box::new(GameOverScreen::new(GameOverCause::WrongInput(input), stats, self.save_data, self.keymap.clone()))));
+        }
+
+        self.fixes += fixes_this_press;
+        Ok(SceneTransition::None)
+    }
+
+    fn try_release(&mut self, input: InputEvent) -> GameResult<SceneTransition> {
         for component in &mut self.components {
-            component.update(&GAME_STATE)?;
-            if component.is_broken() && component.key_object.as_ref().unwrap().frames_existed > 120 {
-                game_over_key = Some(component.fix_key);
+            component.on_key_released(input, &self.keymap)?;
+        }
+        Ok(SceneTransition::None)
+    }
+}
+
+impl Scene for PlayScene {
+    fn tick(&mut self, _ctx: &mut Context) -> GameResult<SceneTransition> {
+        self.frame += 1;
+
+        let mut game_over_action: Option<FixAction> = None;
+        for component in &mut self.components {
+            component.update(&GAME_STATE, &self.keymap)?;
+            if component.is_broken() && component.key_object.as_ref().unwrap().frames_existed > GAME_STATE.grace_period_frames() {
+                game_over_action = Some(component.fix_action);
                 break;
             }
         }
 
-        if game_over_key.is_some() {
-            self.pause(Box::new(GameOverScreen::new(GameOverCause::NotInTime(game_over_key.unwrap()))));
+        if let Some(action) = game_over_action {
+            let stats = self.run_stats();
+            let input = self.input_for_action(action);
+            return Ok(SceneTransition::Replace(Box::new(GameOverScreen::new(GameOverCause::NotInTime(input), stats, self.save_data, self.keymap.clone()))));
         }
 
-        std::thread::yield_now();
-        Ok(())
+        Ok(SceneTransition::None)
     }
 
-    fn key_down_event(&mut self, _ctx: &mut Context, input: KeyInput, _repeat: bool) -> GameResult {
-        if input.keycode.is_some() {
-            if input.keycode.unwrap() == VirtualKeyCode::Escape {
-                process::exit(0);
-            }
+    fn draw(&self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        canvas.draw(&Quad, DrawParam::default().color(Color::BLACK).dest_rect(Rect::new(0., 0., 5000., 5000.)));
+        canvas.draw(&self.background, DrawParam::default().dest_rect(Rect::new(0., 0., 6.4, 6.4)));
 
-            let mut fixed_something = false;
-            for component in &mut self.components {
-                if component.on_key_pressed(input.keycode.as_ref().unwrap()) {
-                    fixed_something = true
-                }
-            }
+        for child in &self.components {
+            child.draw(ctx, canvas)?;
+        }
 
-            if !fixed_something {
-                self.pause(Box::new(GameOverScreen::new(GameOverCause::WrongKey(input.keycode.as_ref().unwrap()))));
+        for child in &self.components {
+            if child.key_object.is_some() {
+                child.key_object.as_ref().unwrap().draw(ctx, canvas)?;
             }
         }
+
         Ok(())
     }
 
-    fn key_up_event(&mut self, _ctx: &mut Context, input: KeyInput) -> GameResult {
-        if input.keycode.is_some() {
-            for component in &mut self.components {
-                component.on_key_released(&input.keycode.unwrap())?;
-            }
+    fn key_down(&mut self, _ctx: &mut Context, input: KeyInput, _repeat: bool) -> GameResult<SceneTransition> {
+        match input.keycode {
+            Some(keycode) => self.try_fix(InputEvent::Key(keycode)),
+            None => Ok(SceneTransition::None),
         }
-        Ok(())
     }
 
-    fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        let mut canvas = Canvas::from_frame(ctx, None);
-        canvas.set_sampler(Sampler::nearest_clamp());
+    fn key_up(&mut self, _ctx: &mut Context, input: KeyInput) -> GameResult<SceneTransition> {
+        match input.keycode {
+            Some(keycode) => self.try_release(InputEvent::Key(keycode)),
+            None => Ok(SceneTransition::None),
+        }
+    }
 
-        if self.menu.is_some() {
-            self.menu.as_ref().unwrap().draw(ctx, &mut canvas)?;
-        } else {
+    fn input_down(&mut self, _ctx: &mut Context, input: InputEvent) -> GameResult<SceneTransition> {
+        self.try_fix(input)
+    }
 
-            canvas.draw(&Quad, DrawParam::default().color(Color::BLACK).dest_rect(Rect::new(0., 0., 5000., 5000.)));
-            canvas.draw(&self.background, DrawParam::default().dest_rect(Rect::new(0., 0., 6.4, 6.4)));
+    fn input_up(&mut self, _ctx: &mut Context, input: InputEvent) -> GameResult<SceneTransition> {
+        self.try_release(input)
+    }
 
-            for child in &self.components {
-                child.draw(ctx, &mut canvas)?;
-            }
+    #[cfg(feature = "debug_overlay")]
+    fn debug_components(&self) -> Vec<crate::debug_overlay::ComponentDebugInfo> {
+        self.components
+            .iter()
+            .enumerate()
+            .map(|(i, component)| crate::debug_overlay::ComponentDebugInfo {
+                label: format!("#{i} ({:?})", component.fix_action),
+                frames_since_broken: component.key_object.as_ref().map(|key| key.frames_existed),
+            })
+            .collect()
+    }
 
-            for child in &self.components {
-                if child.key_object.is_some() {
-                    child.key_object.as_ref().unwrap().draw(ctx, &mut canvas)?;
-                }
+    #[cfg(feature = "debug_overlay")]
+    fn debug_force_break(&mut self, index: usize) {
+        if let Some(component) = self.components.get_mut(index) {
+            if !component.is_broken() {
+                component.mess_up(&GAME_STATE, &self.keymap);
             }
         }
-        
-        canvas.finish(ctx)?;
+    }
 
-        Ok(())
+    #[cfg(feature = "debug_overlay")]
+    fn debug_instant_fix(&mut self, index: usize) {
+        if let Some(component) = self.components.get_mut(index) {
+            component.force_fix();
+        }
+    }
+
+    #[cfg(feature = "debug_overlay")]
+    fn debug_frame(&self) -> usize {
+        self.frame
     }
 }
 
-/// Returns an array of the game objects boxed that needed to be added to the window.
+/// Returns an array of the game objects that need to be added to the play scene, each
+/// carrying the `FixAction` it needs to be fixed with. Key/button resolution happens later, at
+/// input time, via the keymap - not here.
 pub fn create_objects(ctx: &Context) -> [FixableGameObject; 7] {
-    let window = FixableGameObject::new("/window", Vec2::new(1165., 51.), &VirtualKeyCode::W, ctx);
+    let window = FixableGameObject::new("/window", Vec2::new(1165., 51.), FixAction::FixWindow, ctx);
 
-    let milk = FixableGameObject::new("/milk", Vec2::new(1220., 384.), &VirtualKeyCode::M, ctx);
-    let lamp = FixableGameObject::new("/lamp", Vec2::new(1478., 384.), &VirtualKeyCode::L, ctx);
-    let drawer_1 = FixableGameObject::new("/drawer", Vec2::new(1188., 767.), &VirtualKeyCode::D, ctx);
-    let drawer_2 = FixableGameObject::new("/drawer", Vec2::new(1188., 645.), &VirtualKeyCode::D, ctx);
-    let drawer_3 = FixableGameObject::new("/drawer", Vec2::new(1188., 525.), &VirtualKeyCode::D, ctx);
+    let milk = FixableGameObject::new("/milk", Vec2::new(1220., 384.), FixAction::FixMilk, ctx)
+        .with_liquid_surface(LiquidSurface::new(12, 10.));
+    let lamp = FixableGameObject::new("/lamp", Vec2::new(1478., 384.), FixAction::FixLamp, ctx);
+    let drawer_1 = FixableGameObject::new("/drawer", Vec2::new(1188., 767.), FixAction::FixDrawer, ctx);
+    let drawer_2 = FixableGameObject::new("/drawer", Vec2::new(1188., 645.), FixAction::FixDrawer, ctx);
+    let drawer_3 = FixableGameObject::new("/drawer", Vec2::new(1188., 525.), FixAction::FixDrawer, ctx);
 
-    let rug = FixableGameObject::new("/rug", Vec2::new(1343., 935.), &VirtualKeyCode::R, ctx);
+    let rug = FixableGameObject::new("/rug", Vec2::new(1343., 935.), FixAction::FixRug, ctx);
 
     [
-        window, 
+        window,
         milk,
         rug,
         lamp,
-        drawer_1, 
-        drawer_2, 
+        drawer_1,
+        drawer_2,
         drawer_3
     ]
 }
@@ -0,0 +1,102 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use ggez::{
+    glam::Vec2,
+    graphics::{Image, Text},
+    Context, GameResult,
+};
+
+/// Whether "more Rust" flavor-text substitution is active. Off by default; toggled with
+/// [`set_more_rust`].
+static MORE_RUST: AtomicBool = AtomicBool::new(false);
+
+pub fn set_more_rust(enabled: bool) {
+    MORE_RUST.store(enabled, Ordering::Relaxed);
+}
+
+pub fn more_rust_enabled() -> bool {
+    MORE_RUST.load(Ordering::Relaxed)
+}
+
+/// Rewrites `message` into themed "more Rust" flavor text when that mode is enabled, otherwise
+/// returns it unchanged. Centralized here so callers building game-over and popup text don't
+/// need to know the substitution exists.
+pub fn flavor(message: &str) -> String {
+    if !more_rust_enabled() {
+        return message.to_string();
+    }
+
+    message
+        .replace("Game Over", "Segfault (just kidding, this is Rust)")
+        .replace("pressed", "borrowed")
+        .replace("waited too long", "held the borrow too long")
+        .replace("New record!", "New record! Zero unsafe blocks were harmed.")
+}
+
+/// A [`Text`] alongside its measured on-screen dimensions, computed on first use rather than
+/// every draw call. Dimensions only depend on content, font, and scale, none of which change
+/// after construction, so re-measuring every frame is wasted work.
+pub struct MeasuredText {
+    text: Text,
+    dimensions: RefCell<Option<Vec2>>,
+}
+
+impl MeasuredText {
+    /// Creates a new label from `content`, applying the shared game font and `scale`. `content`
+    /// is passed through [`flavor`] so "more Rust" mode can rewrite it transparently.
+    pub fn new(content: impl AsRef<str>, scale: f32) -> Self {
+        let mut text = Text::new(flavor(content.as_ref()));
+        text.set_font("PixeloidSans");
+        text.set_scale(scale);
+
+        Self {
+            text,
+            dimensions: RefCell::new(None),
+        }
+    }
+
+    pub fn text(&self) -> &Text {
+        &self.text
+    }
+
+    /// Returns this label's measured dimensions, computing and caching them on first call.
+    pub fn dimensions(&self, ctx: &Context) -> GameResult<Vec2> {
+        if let Some(dimensions) = *self.dimensions.borrow() {
+            return Ok(dimensions);
+        }
+
+        let dimensions = self.text.measure(&ctx.gfx)?;
+        *self.dimensions.borrow_mut() = Some(dimensions);
+        Ok(dimensions)
+    }
+}
+
+/// An image loaded from `path`, fetched once and reused on every subsequent [`CachedTexture::get`]
+/// instead of being re-read from disk every frame.
+pub struct CachedTexture {
+    path: String,
+    image: RefCell<Option<Image>>,
+}
+
+impl CachedTexture {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            image: RefCell::new(None),
+        }
+    }
+
+    /// Returns the cached image, loading it from `self.path` the first time it's requested.
+    /// `Image` wraps its GPU texture behind a refcount, so the clone here is cheap.
+    pub fn get(&self, ctx: &Context) -> Image {
+        if let Some(image) = &*self.image.borrow() {
+            return image.clone();
+        }
+
+        let image = Image::from_path(ctx, &self.path).unwrap();
+        *self.image.borrow_mut() = Some(image.clone());
+        image
+    }
+}
@@ -0,0 +1,56 @@
+use ggez::{
+    glam::Vec2,
+    graphics::{Canvas, Color, DrawParam, Quad, Rect, Text},
+    input::keyboard::KeyInput,
+    Context, GameResult,
+};
+
+use crate::{
+    keymap::Keymap,
+    scene::{Scene, SceneTransition},
+    screen::PlayScene,
+    util::SaveData,
+};
+
+/// The first scene shown when the game launches; waits for any key to start a run.
+pub struct TitleScene {
+    title: Text,
+    prompt: Text,
+    keymap: Keymap,
+    save_data: SaveData,
+}
+
+impl TitleScene {
+    pub fn new(keymap: Keymap, save_data: SaveData) -> Self {
+        let mut title = Text::new("Fixit");
+        title.set_font("PixeloidSans");
+        title.set_scale(150.);
+
+        let mut prompt = Text::new("Press any key to start");
+        prompt.set_font("PixeloidSans");
+        prompt.set_scale(60.);
+
+        Self { title, prompt, keymap, save_data }
+    }
+}
+
+impl Scene for TitleScene {
+    fn draw(&self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        canvas.draw(&Quad, DrawParam::default().color(Color::BLACK).dest_rect(Rect::new(0., 0., 1920., 1080.)));
+
+        let title_dimensions = self.title.measure(&ctx.gfx)?;
+        canvas.draw(&self.title, DrawParam::new().dest(Vec2::new(1920. / 2. - title_dimensions.x / 2., 350.)));
+
+        let prompt_dimensions = self.prompt.measure(&ctx.gfx)?;
+        canvas.draw(&self.prompt, DrawParam::new().dest(Vec2::new(1920. / 2. - prompt_dimensions.x / 2., 550.)));
+
+        Ok(())
+    }
+
+    fn key_down(&mut self, ctx: &mut Context, input: KeyInput, _repeat: bool) -> GameResult<SceneTransition> {
+        if input.keycode.is_some() {
+            return Ok(SceneTransition::Replace(Box::new(PlayScene::new(ctx, &self.keymap, self.save_data))));
+        }
+        Ok(SceneTransition::None)
+    }
+}
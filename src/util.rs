@@ -1,4 +1,6 @@
-use std::{env, path::PathBuf};
+use std::{env, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
 
 pub fn get_resource_dir() -> PathBuf {
     if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
@@ -8,4 +10,80 @@ pub fn get_resource_dir() -> PathBuf {
     } else {
         PathBuf::from("./resources")
     }
-}
\ No newline at end of file
+}
+
+/// Returns a user-writable directory for persisting save data, separate from the (potentially
+/// read-only, installed) resource directory.
+pub fn get_save_dir() -> PathBuf {
+    if let Ok(xdg_data_home) = env::var("XDG_DATA_HOME") {
+        let mut path = PathBuf::from(xdg_data_home);
+        path.push("fixit");
+        return path;
+    }
+
+    if let Ok(home) = env::var("HOME") {
+        let mut path = PathBuf::from(home);
+        path.push(".local/share/fixit");
+        return path;
+    }
+
+    PathBuf::from("./save")
+}
+
+/// The player's persisted progress across runs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SaveData {
+    pub longest_survival_frames: usize,
+    pub total_fixes: u32,
+    pub highest_chance_of_breaking: f32,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            longest_survival_frames: 0,
+            total_fixes: 0,
+            highest_chance_of_breaking: 0.,
+        }
+    }
+}
+
+/// A tiny filesystem abstraction for persisting [`SaveData`], so a future platform port (e.g. a
+/// mobile target with sandboxed storage) can swap the backing store without touching game logic.
+pub trait SaveStore {
+    /// Reads the stored save data, or `None` if there is none yet (or it couldn't be read).
+    fn read(&self) -> Option<SaveData>;
+
+    /// Persists `data`, overwriting whatever was previously stored.
+    fn write(&self, data: &SaveData);
+}
+
+/// The default [`SaveStore`], backed by a JSON file in [`get_save_dir`].
+pub struct FileSaveStore {
+    path: PathBuf,
+}
+
+impl FileSaveStore {
+    pub fn new() -> Self {
+        let mut path = get_save_dir();
+        path.push("save.json");
+        Self { path }
+    }
+}
+
+impl SaveStore for FileSaveStore {
+    fn read(&self) -> Option<SaveData> {
+        let contents = fs::read_to_string(&self.path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    fn write(&self, data: &SaveData) {
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+
+        if let Ok(contents) = serde_json::to_string_pretty(data) {
+            let _ = fs::write(&self.path, contents);
+        }
+    }
+}